@@ -1,15 +1,373 @@
 /// macOS Accessibility API observer.
 ///
-/// Watches for AXSecureTextField focus events across all apps and emits
-/// the field's screen coordinates so the overlay can appear nearby.
-///
-/// TODO: Implement real AXObserver using the accessibility-sys crate.
-///       For now this is a stub â€” autofill in native apps is triggered
-///       by the browser extension instead.
-use tauri::AppHandle;
-
-pub fn start_ax_observer(_app: AppHandle) {
-    // Real implementation will use AXObserverCreate + AXObserverAddNotification
-    // to watch for kAXFocusedUIElementChangedNotification system-wide and check
-    // if the newly focused element has role AXSecureTextField.
+/// Watches for `AXSecureTextField` focus events across every running
+/// application and emits the field's screen coordinates so the overlay can
+/// appear nearby. Also exposes a command to inject the chosen credential
+/// back into the focused field once the user picks one from the overlay.
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::sync::Mutex;
+
+use accessibility_sys::{
+    kAXErrorSuccess, kAXFocusedUIElementChangedNotification, kAXPositionAttribute,
+    kAXRoleAttribute, kAXSizeAttribute, kAXSubroleAttribute, kAXValueAttribute,
+    kAXValueCGPointType, kAXValueCGSizeType, AXIsProcessTrustedWithOptions, AXObserverAddNotification,
+    AXObserverCreate, AXObserverGetRunLoopSource, AXObserverRef, AXUIElementCopyAttributeValue,
+    AXUIElementCreateApplication, AXUIElementRef, AXUIElementSetAttributeValue,
+    AXValueGetValue,
+};
+use cocoa::appkit::NSWorkspace;
+use cocoa::base::{id, nil};
+use core_foundation::base::{CFRelease, CFType, CFTypeRef, TCFType};
+use core_foundation::boolean::CFBoolean;
+use core_foundation::dictionary::CFDictionary;
+use core_foundation::runloop::{kCFRunLoopDefaultMode, CFRunLoop};
+use core_foundation::string::CFString;
+use core_graphics::display::CGPoint;
+use core_graphics::event::{CGEvent, CGEventTapLocation};
+use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
+use core_graphics::geometry::CGSize;
+use objc::runtime::{Object, Sel};
+use objc::{class, msg_send, sel, sel_impl};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Screen coordinates of a focused secure field, forwarded to the frontend
+/// so the overlay can be positioned just below it.
+#[derive(Clone, serde::Serialize)]
+struct FocusedFieldPosition {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+/// An observer we've registered for a pid, plus the refcon `Box<AppHandle>`
+/// handed to `AXObserverAddNotification` for it — kept so we can free both
+/// when the app quits instead of leaking one `AppHandle` per watched app.
+struct WatchedApp {
+    observer: AXObserverRef,
+    refcon: *mut AppHandle,
+}
+
+/// Observers we've registered, keyed by pid, so we can tear them down again
+/// when an application quits.
+struct WatchedApps(Mutex<HashMap<i32, WatchedApp>>);
+
+unsafe impl Send for WatchedApps {}
+unsafe impl Sync for WatchedApps {}
+
+/// Entry point called from `run()`'s `setup` closure.
+///
+/// Bails out (emitting `accessibility:permission-missing`) if the user
+/// hasn't granted accessibility access yet, otherwise attaches an
+/// `AXObserver` to every currently running application and keeps watching
+/// for new ones.
+pub fn start_ax_observer(app: AppHandle) {
+    if !is_trusted(true) {
+        let _ = app.emit("accessibility:permission-missing", ());
+        return;
+    }
+
+    app.manage(WatchedApps(Mutex::new(HashMap::new())));
+
+    unsafe {
+        for pid in running_application_pids() {
+            watch_application(&app, pid);
+        }
+        watch_for_app_launch_and_terminate(app);
+    }
+}
+
+/// Wraps `AXIsProcessTrustedWithOptions`, optionally prompting the user with
+/// the system "grant accessibility access" dialog.
+fn is_trusted(prompt: bool) -> bool {
+    unsafe {
+        let key = CFString::new("AXTrustedCheckOptionPrompt");
+        let value = CFBoolean::from(prompt);
+        let options = CFDictionary::from_CFType_pairs(&[(key.as_CFType(), value.as_CFType())]);
+        AXIsProcessTrustedWithOptions(options.as_concrete_TypeRef() as CFTypeRef)
+    }
+}
+
+/// Returns the pid of every app in `NSWorkspace.runningApplications` that
+/// has a regular UI presence (skips background-only agents).
+unsafe fn running_application_pids() -> Vec<i32> {
+    let workspace: id = NSWorkspace::sharedWorkspace(nil);
+    let apps: id = msg_send![workspace, runningApplications];
+    let count: usize = msg_send![apps, count];
+
+    let mut pids = Vec::with_capacity(count);
+    for i in 0..count {
+        let running_app: id = msg_send![apps, objectAtIndex: i];
+        let activation_policy: i64 = msg_send![running_app, activationPolicy];
+        if activation_policy != 0 {
+            // Not NSApplicationActivationPolicyRegular — skip background agents.
+            continue;
+        }
+        let pid: i32 = msg_send![running_app, processIdentifier];
+        pids.push(pid);
+    }
+    pids
+}
+
+/// Creates an `AXObserver` for `pid`, subscribes it to focus-change
+/// notifications, and wires its run loop source into the main run loop.
+unsafe fn watch_application(app: &AppHandle, pid: i32) {
+    let state = app.state::<WatchedApps>();
+    if state.0.lock().unwrap().contains_key(&pid) {
+        return;
+    }
+
+    let mut observer: AXObserverRef = std::ptr::null_mut();
+    let err = AXObserverCreate(pid, ax_observer_callback, &mut observer);
+    if err != kAXErrorSuccess || observer.is_null() {
+        return;
+    }
+
+    let app_element: AXUIElementRef = AXUIElementCreateApplication(pid);
+    let notification = CFString::new(kAXFocusedUIElementChangedNotification);
+    let refcon = Box::into_raw(Box::new(app.clone()));
+
+    AXObserverAddNotification(
+        observer,
+        app_element,
+        notification.as_concrete_TypeRef(),
+        refcon as *mut c_void,
+    );
+
+    let source = AXObserverGetRunLoopSource(observer);
+    CFRunLoop::get_current().add_source(source, kCFRunLoopDefaultMode);
+
+    state.0.lock().unwrap().insert(pid, WatchedApp { observer, refcon });
+    CFRelease(app_element as CFTypeRef);
+}
+
+/// Tears down the observer for `pid`, called once its app has terminated.
+///
+/// Frees both the `AXObserverRef` and the `Box<AppHandle>` refcon it was
+/// registered with, so watching apps come and go doesn't leak one
+/// `AppHandle` per app over the life of the session.
+unsafe fn unwatch_application(app: &AppHandle, pid: i32) {
+    let state = app.state::<WatchedApps>();
+    if let Some(watched) = state.0.lock().unwrap().remove(&pid) {
+        CFRelease(watched.observer as CFTypeRef);
+        drop(Box::from_raw(watched.refcon));
+    }
+}
+
+/// Registers for `NSWorkspace` launch/terminate notifications so observers
+/// are added and removed as applications come and go.
+unsafe fn watch_for_app_launch_and_terminate(app: AppHandle) {
+    extern "C" fn handle_launch(this: &Object, _cmd: Sel, notification: id) {
+        handle_workspace_notification(this, notification, true);
+    }
+    extern "C" fn handle_terminate(this: &Object, _cmd: Sel, notification: id) {
+        handle_workspace_notification(this, notification, false);
+    }
+
+    fn handle_workspace_notification(this: &Object, notification: id, launched: bool) {
+        unsafe {
+            let app_handle_ptr: *mut c_void = *this.get_ivar("appHandle");
+            let app = &*(app_handle_ptr as *const AppHandle);
+
+            let user_info: id = msg_send![notification, userInfo];
+            let running_app: id = msg_send![user_info, objectForKey: cocoa::foundation::NSString::alloc(nil).init_str("NSWorkspaceApplicationKey")];
+            let pid: i32 = msg_send![running_app, processIdentifier];
+
+            if launched {
+                watch_application(app, pid);
+            } else {
+                unwatch_application(app, pid);
+            }
+        }
+    }
+
+    let superclass = class!(NSObject);
+    let mut decl = objc::declare::ClassDecl::new("KeynestWorkspaceWatcher", superclass)
+        .expect("KeynestWorkspaceWatcher class already registered");
+    decl.add_ivar::<*mut c_void>("appHandle");
+    decl.add_method(
+        sel!(handleLaunch:),
+        handle_launch as extern "C" fn(&Object, Sel, id),
+    );
+    decl.add_method(
+        sel!(handleTerminate:),
+        handle_terminate as extern "C" fn(&Object, Sel, id),
+    );
+    let class = decl.register();
+
+    let watcher: *mut Object = msg_send![class, new];
+    // `watcher` and the `AppHandle` it needs both live for the rest of the
+    // process — there's no corresponding "stop watching launches" path to
+    // free this against, so this is a single intentional leak, not a
+    // per-event one like the per-pid observer refcons above.
+    let app_ptr = Box::leak(Box::new(app)) as *mut AppHandle as *mut c_void;
+    (*watcher).set_ivar("appHandle", app_ptr);
+
+    let workspace: id = NSWorkspace::sharedWorkspace(nil);
+    let center: id = msg_send![workspace, notificationCenter];
+    let launch_name =
+        cocoa::foundation::NSString::alloc(nil).init_str("NSWorkspaceDidLaunchApplicationNotification");
+    let terminate_name = cocoa::foundation::NSString::alloc(nil)
+        .init_str("NSWorkspaceDidTerminateApplicationNotification");
+
+    let _: () = msg_send![center, addObserver: watcher selector: sel!(handleLaunch:) name: launch_name object: nil];
+    let _: () = msg_send![center, addObserver: watcher selector: sel!(handleTerminate:) name: terminate_name object: nil];
+}
+
+/// `AXObserverCreate` callback, fired whenever the focused UI element
+/// changes in a watched application.
+extern "C" fn ax_observer_callback(
+    _observer: AXObserverRef,
+    element: AXUIElementRef,
+    _notification: accessibility_sys::CFStringRef,
+    refcon: *mut c_void,
+) {
+    unsafe {
+        let app = &*(refcon as *const AppHandle);
+        if let Some(position) = secure_field_position(element) {
+            let _ = app.emit("autofill:field-focused", position);
+        }
+    }
+}
+
+/// If `element` is a secure text field (or a text field with a password
+/// subrole), returns its on-screen position and size.
+unsafe fn secure_field_position(element: AXUIElementRef) -> Option<FocusedFieldPosition> {
+    if !is_secure_field(element) {
+        return None;
+    }
+
+    let mut point = CGPoint::new(0.0, 0.0);
+    let mut size = CGSize::new(0.0, 0.0);
+
+    if !copy_ax_value(element, kAXPositionAttribute, kAXValueCGPointType, &mut point as *mut _ as *mut c_void) {
+        return None;
+    }
+    if !copy_ax_value(element, kAXSizeAttribute, kAXValueCGSizeType, &mut size as *mut _ as *mut c_void) {
+        return None;
+    }
+
+    Some(FocusedFieldPosition {
+        x: point.x,
+        y: point.y,
+        width: size.width,
+        height: size.height,
+    })
+}
+
+unsafe fn is_secure_field(element: AXUIElementRef) -> bool {
+    let role = copy_ax_string_attribute(element, kAXRoleAttribute);
+    if role.as_deref() == Some("AXSecureTextField") {
+        return true;
+    }
+    if role.as_deref() == Some("AXTextField") {
+        let subrole = copy_ax_string_attribute(element, kAXSubroleAttribute);
+        return subrole.as_deref() == Some("AXSecureTextField") || subrole.as_deref() == Some("AXPasswordField");
+    }
+    false
+}
+
+unsafe fn copy_ax_string_attribute(element: AXUIElementRef, attribute: accessibility_sys::CFStringRef) -> Option<String> {
+    let mut value: CFTypeRef = std::ptr::null_mut();
+    let attr = CFString::wrap_under_get_rule(attribute);
+    let err = AXUIElementCopyAttributeValue(element, attr.as_concrete_TypeRef(), &mut value);
+    if err != kAXErrorSuccess || value.is_null() {
+        return None;
+    }
+    let cf_string = CFType::wrap_under_create_rule(value);
+    cf_string.downcast::<CFString>().map(|s| s.to_string())
+}
+
+unsafe fn copy_ax_value(
+    element: AXUIElementRef,
+    attribute: accessibility_sys::CFStringRef,
+    value_type: accessibility_sys::AXValueType,
+    out: *mut c_void,
+) -> bool {
+    let mut value: CFTypeRef = std::ptr::null_mut();
+    let attr = CFString::wrap_under_get_rule(attribute);
+    let err = AXUIElementCopyAttributeValue(element, attr.as_concrete_TypeRef(), &mut value);
+    if err != kAXErrorSuccess || value.is_null() {
+        return false;
+    }
+    // `value` is a +1 create-rule reference; wrapping it releases it when
+    // this function returns instead of leaking it on every call.
+    let value = CFType::wrap_under_create_rule(value);
+    AXValueGetValue(value.as_CFTypeRef() as accessibility_sys::AXValueRef, value_type, out)
+}
+
+/// Injects `credential` into whatever secure field is currently focused in
+/// the frontmost application.
+///
+/// Tries the fast path first (`AXUIElementSetAttributeValue` on
+/// `kAXValueAttribute`); if the field rejects programmatic writes (common
+/// for hardened password managers' own fields, and some Electron apps),
+/// falls back to synthesizing keystrokes with `CGEvent`.
+#[tauri::command]
+pub fn fill_focused_field(credential: String) -> Result<(), String> {
+    if !is_trusted(false) {
+        return Err("accessibility permission not granted".into());
+    }
+
+    unsafe {
+        let system_wide = accessibility_sys::AXUIElementCreateSystemWide();
+        let mut focused: CFTypeRef = std::ptr::null_mut();
+        let attr = CFString::new(kAXFocusedUIElementAttribute());
+        let err = AXUIElementCopyAttributeValue(system_wide, attr.as_concrete_TypeRef(), &mut focused);
+        CFRelease(system_wide as CFTypeRef);
+
+        if err != kAXErrorSuccess || focused.is_null() {
+            return Err("no focused UI element".into());
+        }
+        // `focused` is a +1 create-rule reference; wrapping it releases it
+        // once `element` goes out of scope instead of leaking it per call.
+        let focused = CFType::wrap_under_create_rule(focused);
+        let element = focused.as_CFTypeRef() as AXUIElementRef;
+
+        // Focus can move between the field-focused event and the user
+        // picking a credential (a different app, a chat box, a terminal).
+        // Re-check it's still a secure field right before writing, the
+        // same gate the observer applies when it first reports the field.
+        if !is_secure_field(element) {
+            return Err("focused element is no longer a secure field".into());
+        }
+
+        if set_value_directly(element, &credential) {
+            return Ok(());
+        }
+        type_via_keystrokes(&credential);
+        Ok(())
+    }
+}
+
+fn kAXFocusedUIElementAttribute() -> &'static str {
+    "AXFocusedUIElement"
+}
+
+unsafe fn set_value_directly(element: AXUIElementRef, value: &str) -> bool {
+    let attr = CFString::new(kAXValueAttribute);
+    let cf_value = CFString::new(value);
+    let err = AXUIElementSetAttributeValue(
+        element,
+        attr.as_concrete_TypeRef(),
+        cf_value.as_concrete_TypeRef() as CFTypeRef,
+    );
+    err == kAXErrorSuccess
+}
+
+unsafe fn type_via_keystrokes(value: &str) {
+    let Ok(source) = CGEventSource::new(CGEventSourceStateID::HIDSystemState) else {
+        return;
+    };
+    for ch in value.encode_utf16() {
+        if let Ok(key_down) = CGEvent::new_keyboard_event(source.clone(), 0, true) {
+            key_down.set_string_from_utf16_unchecked(&[ch]);
+            key_down.post(CGEventTapLocation::HID);
+        }
+        if let Ok(key_up) = CGEvent::new_keyboard_event(source.clone(), 0, false) {
+            key_up.set_string_from_utf16_unchecked(&[ch]);
+            key_up.post(CGEventTapLocation::HID);
+        }
+    }
 }
@@ -0,0 +1,102 @@
+/// macOS Dock/app-switcher visibility.
+///
+/// As an overlay/menubar-style app, Keynest has no business showing a Dock
+/// icon, a menu bar, or appearing in Cmd+Tab. We default to the
+/// `Accessory` activation policy, but expose a setting for users who do
+/// want a traditional Dock presence.
+use serde::{Deserialize, Serialize};
+use std::fs;
+use tauri::{AppHandle, Manager, Window};
+
+const SETTINGS_FILE: &str = "activation-policy.json";
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ActivationPolicy {
+    Accessory,
+    Regular,
+}
+
+impl Default for ActivationPolicy {
+    fn default() -> Self {
+        ActivationPolicy::Accessory
+    }
+}
+
+/// Loads the persisted activation policy, defaulting to `Accessory`.
+pub fn load(app: &AppHandle) -> ActivationPolicy {
+    app.path()
+        .app_config_dir()
+        .ok()
+        .map(|dir| dir.join(SETTINGS_FILE))
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn save(app: &AppHandle, policy: ActivationPolicy) {
+    let Ok(dir) = app.path().app_config_dir() else {
+        return;
+    };
+    let _ = fs::create_dir_all(&dir);
+    if let Ok(json) = serde_json::to_string(&policy) {
+        let _ = fs::write(dir.join(SETTINGS_FILE), json);
+    }
+}
+
+/// Applies `policy` to the running app via `NSApplication.setActivationPolicy`.
+#[cfg(target_os = "macos")]
+pub fn apply(policy: ActivationPolicy) {
+    use cocoa::appkit::{NSApp, NSApplicationActivationPolicy};
+    use objc::{msg_send, sel, sel_impl};
+
+    let ns_policy = match policy {
+        ActivationPolicy::Accessory => {
+            NSApplicationActivationPolicy::NSApplicationActivationPolicyAccessory
+        }
+        ActivationPolicy::Regular => {
+            NSApplicationActivationPolicy::NSApplicationActivationPolicyRegular
+        }
+    };
+    unsafe {
+        let app = NSApp();
+        let _: () = msg_send![app, setActivationPolicy: ns_policy as i64];
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn apply(_policy: ActivationPolicy) {}
+
+/// Command the settings UI calls to flip between Dock-hidden and
+/// Dock-visible, re-applying it immediately instead of requiring a restart.
+#[tauri::command]
+pub fn set_activation_policy(app: AppHandle, regular: bool) {
+    let policy = if regular {
+        ActivationPolicy::Regular
+    } else {
+        ActivationPolicy::Accessory
+    };
+    save(&app, policy);
+    apply(policy);
+}
+
+/// Brings Keynest to the front before focusing `window`.
+///
+/// Under the `Accessory` activation policy macOS doesn't automatically
+/// activate the app when one of its windows is shown, so `set_focus` alone
+/// can leave the overlay visible but not key. Activate the app explicitly
+/// first.
+#[cfg(target_os = "macos")]
+pub fn activate_and_focus(window: &Window) {
+    use cocoa::appkit::NSApp;
+    use objc::{msg_send, sel, sel_impl};
+    unsafe {
+        let app = NSApp();
+        let _: () = msg_send![app, activateIgnoringOtherApps: true];
+    }
+    let _ = window.set_focus();
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn activate_and_focus(window: &Window) {
+    let _ = window.set_focus();
+}
@@ -0,0 +1,208 @@
+/// User-configurable global hotkeys.
+///
+/// Loads an array of `{action, accelerator}` bindings from disk (falling
+/// back to sane defaults on first run), registers all of them with
+/// `tauri-plugin-global-shortcut`, and exposes `rebind_hotkey` so the
+/// settings UI can let power users pick their own combos without
+/// restarting the app.
+use crate::positioning::{self, AnchorMode};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Mutex;
+use tauri::{App, AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+const BINDINGS_FILE: &str = "hotkeys.json";
+
+/// Named actions a hotkey binding can trigger. Kept separate from the
+/// accelerator string so the settings UI can show a stable list of
+/// actions even while the user is mid-rebind.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum HotkeyAction {
+    ToggleOverlay,
+    FillCurrentField,
+    LockVault,
+    OpenSearch,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Binding {
+    action: HotkeyAction,
+    accelerator: String,
+}
+
+fn default_bindings() -> Vec<Binding> {
+    vec![
+        Binding {
+            action: HotkeyAction::ToggleOverlay,
+            accelerator: "CmdOrCtrl+Shift+K".into(),
+        },
+        Binding {
+            action: HotkeyAction::FillCurrentField,
+            accelerator: "CmdOrCtrl+Shift+L".into(),
+        },
+    ]
+}
+
+/// Currently-registered accelerator for each action, so `rebind_hotkey`
+/// knows what to unregister and `save_bindings` knows what to persist.
+struct Hotkeys {
+    bindings: Mutex<HashMap<HotkeyAction, String>>,
+}
+
+/// Loads persisted bindings (or the defaults) and registers every one of
+/// them. A binding that fails to parse or register emits
+/// `hotkeys:register-failed` rather than aborting the rest.
+pub fn register_hotkeys(app: &mut App) -> Result<(), Box<dyn std::error::Error>> {
+    app.handle()
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())?;
+
+    app.manage(Hotkeys {
+        bindings: Mutex::new(HashMap::new()),
+    });
+
+    for binding in load_bindings(app.handle()) {
+        if let Err(err) = register_one(app.handle(), &binding) {
+            let _ = app.handle().emit(
+                "hotkeys:register-failed",
+                serde_json::json!({
+                    "action": binding.action,
+                    "accelerator": binding.accelerator,
+                    "error": err.to_string(),
+                }),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn register_one(app: &AppHandle, binding: &Binding) -> Result<(), Box<dyn std::error::Error>> {
+    let shortcut: Shortcut = binding.accelerator.parse()?;
+    let action = binding.action;
+
+    app.global_shortcut()
+        .on_shortcut(shortcut, move |app, _shortcut, event| {
+            if event.state == ShortcutState::Pressed {
+                dispatch(app, action);
+            }
+        })?;
+
+    let state = app.state::<Hotkeys>();
+    state
+        .bindings
+        .lock()
+        .unwrap()
+        .insert(action, binding.accelerator.clone());
+    save_bindings(app);
+    Ok(())
+}
+
+/// Runs the effect bound to `action`. `ToggleOverlay` is handled natively
+/// since it's purely window-manager state; the others are surfaced as
+/// events because they need the frontend (vault unlock state, the
+/// credential search UI, the in-progress autofill pipeline).
+fn dispatch(app: &AppHandle, action: HotkeyAction) {
+    match action {
+        HotkeyAction::ToggleOverlay => {
+            if let Some(window) = app.get_webview_window("overlay") {
+                if window.is_visible().unwrap_or(false) {
+                    let _ = window.hide();
+                } else {
+                    let _ = positioning::position_overlay(&window, AnchorMode::LastPosition);
+                    let _ = window.show();
+                    crate::activation::activate_and_focus(&window);
+                }
+            }
+        }
+        HotkeyAction::FillCurrentField => {
+            let _ = app.emit("hotkeys:fill-current-field", ());
+        }
+        HotkeyAction::LockVault => {
+            let _ = app.emit("vault:lock", ());
+        }
+        HotkeyAction::OpenSearch => {
+            // Summon the overlay right next to the pointer rather than its
+            // last remembered spot — the user triggered this from wherever
+            // they're currently working, so that's where it should appear.
+            if let Some(window) = app.get_webview_window("overlay") {
+                let _ = positioning::position_overlay(&window, AnchorMode::NearCursor);
+                let _ = window.show();
+                crate::activation::activate_and_focus(&window);
+            }
+            let _ = app.emit("hotkeys:open-search", ());
+        }
+    }
+}
+
+/// Unregisters the shortcut currently bound to `action` (if any), then
+/// validates and registers `accelerator` in its place. Rejects accelerators
+/// that are already bound to a *different* action instead of silently
+/// stealing them.
+#[tauri::command]
+pub fn rebind_hotkey(app: AppHandle, action: HotkeyAction, accelerator: String) -> Result<(), String> {
+    let shortcut: Shortcut = accelerator
+        .parse()
+        .map_err(|e| format!("invalid accelerator '{accelerator}': {e}"))?;
+
+    // Unregister this action's current shortcut *before* checking for
+    // conflicts, so rebinding an action to the accelerator it already
+    // holds isn't rejected as "bound to another action".
+    let state = app.state::<Hotkeys>();
+    let previous = state.bindings.lock().unwrap().get(&action).cloned();
+    if let Some(previous) = &previous {
+        if let Ok(old_shortcut) = previous.parse::<Shortcut>() {
+            let _ = app.global_shortcut().unregister(old_shortcut);
+        }
+    }
+
+    if app.global_shortcut().is_registered(shortcut.clone()) {
+        // Put the old binding (and its handler) back before bailing out.
+        if let Some(previous) = previous {
+            let _ = register_one(
+                &app,
+                &Binding {
+                    action,
+                    accelerator: previous,
+                },
+            );
+        }
+        return Err(format!("'{accelerator}' is already bound to another action"));
+    }
+
+    register_one(&app, &Binding { action, accelerator }).map_err(|e| e.to_string())
+}
+
+fn bindings_path(app: &AppHandle) -> Option<std::path::PathBuf> {
+    let dir = app.path().app_config_dir().ok()?;
+    fs::create_dir_all(&dir).ok()?;
+    Some(dir.join(BINDINGS_FILE))
+}
+
+fn load_bindings(app: &AppHandle) -> Vec<Binding> {
+    bindings_path(app)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_else(default_bindings)
+}
+
+fn save_bindings(app: &AppHandle) {
+    let Some(path) = bindings_path(app) else {
+        return;
+    };
+    let state = app.state::<Hotkeys>();
+    let bindings: Vec<Binding> = state
+        .bindings
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(action, accelerator)| Binding {
+            action: *action,
+            accelerator: accelerator.clone(),
+        })
+        .collect();
+    if let Ok(json) = serde_json::to_string(&bindings) {
+        let _ = fs::write(path, json);
+    }
+}
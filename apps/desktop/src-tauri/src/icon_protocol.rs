@@ -0,0 +1,147 @@
+/// `keynest://` asset protocol for serving cached login-site favicons.
+///
+/// The overlay renders a small icon next to each vault entry, but we don't
+/// want the webview making arbitrary outbound requests to fetch them —
+/// favicon fetch/refresh is scheduled and sandboxed in the Rust layer
+/// instead, and the webview only ever reads back from this local cache via
+/// `keynest://icon/<entry-id>`.
+use std::fs;
+use std::path::PathBuf;
+use tauri::http::{status::StatusCode, Request, Response};
+use tauri::Manager;
+
+const FAVICON_CACHE_DIR: &str = "icon-cache";
+
+/// Handles a single `keynest://icon/<entry-id>` request, reading from the
+/// on-disk favicon cache and honoring `Range` requests so larger cached
+/// assets can stream efficiently.
+pub fn handle(app: &tauri::AppHandle, request: Request<Vec<u8>>) -> Response<Vec<u8>> {
+    if request.uri().host() != Some("icon") {
+        return not_found();
+    }
+    let Some(entry_id) = entry_id_from_uri(request.uri().path()) else {
+        return not_found();
+    };
+
+    let Some((bytes, content_type)) = load_cached_icon(app, &entry_id) else {
+        return serve_monogram(&entry_id);
+    };
+
+    match request.headers().get("Range").and_then(|v| v.to_str().ok()) {
+        Some(range) => serve_range(&bytes, &content_type, range),
+        None => serve_full(&bytes, &content_type),
+    }
+}
+
+/// Extracts the entry id from the request path and validates it against
+/// the vault's entry-id format (`[A-Za-z0-9_-]+`) before it ever touches
+/// the filesystem — rejecting `/`, `..`, and any other path-traversal
+/// payload outright rather than trying to sanitize them.
+fn entry_id_from_uri(path: &str) -> Option<String> {
+    let id = path.trim_start_matches('/');
+    if !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        Some(id.to_string())
+    } else {
+        None
+    }
+}
+
+fn cache_dir(app: &tauri::AppHandle) -> Option<PathBuf> {
+    app.path().app_cache_dir().ok().map(|dir| dir.join(FAVICON_CACHE_DIR))
+}
+
+/// Looks up a cached favicon by entry id, trying the extensions we know we
+/// might have written on refresh. Returns the raw bytes plus the MIME type
+/// to serve them as.
+fn load_cached_icon(app: &tauri::AppHandle, entry_id: &str) -> Option<(Vec<u8>, &'static str)> {
+    let dir = cache_dir(app)?;
+    for (ext, content_type) in [("png", "image/png"), ("ico", "image/x-icon"), ("jpg", "image/jpeg")] {
+        let path = dir.join(format!("{entry_id}.{ext}"));
+        if let Ok(bytes) = fs::read(&path) {
+            return Some((bytes, content_type));
+        }
+    }
+    None
+}
+
+fn serve_full(bytes: &[u8], content_type: &str) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", content_type)
+        .header("Content-Length", bytes.len().to_string())
+        .header("Accept-Ranges", "bytes")
+        .body(bytes.to_vec())
+        .unwrap_or_else(|_| not_found())
+}
+
+/// Parses a single `bytes=start-end` range (the only form browsers and
+/// webviews actually send for media) and returns a `206 Partial Content`
+/// slice, falling back to a full `200` response if the header doesn't
+/// parse or the range is out of bounds.
+fn serve_range(bytes: &[u8], content_type: &str, range_header: &str) -> Response<Vec<u8>> {
+    let total = bytes.len();
+    let Some(parsed) = parse_byte_range(range_header, total) else {
+        return serve_full(bytes, content_type);
+    };
+    let (start, end) = parsed;
+
+    Response::builder()
+        .status(StatusCode::PARTIAL_CONTENT)
+        .header("Content-Type", content_type)
+        .header("Content-Length", (end - start + 1).to_string())
+        .header("Content-Range", format!("bytes {start}-{end}/{total}"))
+        .header("Accept-Ranges", "bytes")
+        .body(bytes[start..=end].to_vec())
+        .unwrap_or_else(|_| not_found())
+}
+
+/// Parses `bytes=start-end`, `bytes=start-`, or `bytes=-suffix_length` into
+/// an inclusive `(start, end)` byte range clamped to `total`.
+fn parse_byte_range(header: &str, total: usize) -> Option<(usize, usize)> {
+    let spec = header.strip_prefix("bytes=")?;
+    let (start_str, end_str) = spec.split_once('-')?;
+
+    let (start, end) = if start_str.is_empty() {
+        let suffix_len: usize = end_str.parse().ok()?;
+        if suffix_len == 0 {
+            return None;
+        }
+        (total.saturating_sub(suffix_len), total.checked_sub(1)?)
+    } else {
+        let start: usize = start_str.parse().ok()?;
+        let end = if end_str.is_empty() {
+            total.checked_sub(1)?
+        } else {
+            end_str.parse::<usize>().ok()?.min(total.checked_sub(1)?)
+        };
+        (start, end)
+    };
+
+    if start > end {
+        return None;
+    }
+    Some((start, end))
+}
+
+/// Entries with no cached icon yet (first sight of a site, or offline
+/// before the background refresh has run) fall back to a generated
+/// monogram instead of a broken image.
+fn serve_monogram(entry_id: &str) -> Response<Vec<u8>> {
+    let letter = entry_id.chars().next().unwrap_or('?').to_uppercase().to_string();
+    let svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="32" height="32"><rect width="32" height="32" rx="6" fill="#5b5bd6"/><text x="16" y="22" font-size="16" text-anchor="middle" fill="white" font-family="sans-serif">{letter}</text></svg>"#
+    );
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", "image/svg+xml")
+        .header("Content-Length", svg.len().to_string())
+        .body(svg.into_bytes())
+        .unwrap_or_else(|_| not_found())
+}
+
+fn not_found() -> Response<Vec<u8>> {
+    Response::builder()
+        .status(StatusCode::NOT_FOUND)
+        .body(Vec::new())
+        .unwrap()
+}
@@ -1,17 +1,21 @@
-mod overlay;
+mod activation;
+mod hotkeys;
+mod icon_protocol;
+mod positioning;
 mod tray;
 
 #[cfg(target_os = "macos")]
 mod accessibility;
 
+use positioning::AnchorMode;
 use tauri::{AppHandle, Manager};
 
 #[tauri::command]
 fn show_overlay(app: AppHandle, x: f64, y: f64) {
     if let Some(window) = app.get_webview_window("overlay") {
-        let _ = window.set_position(tauri::PhysicalPosition::new(x as i32, y as i32));
+        let _ = positioning::position_overlay(&window, AnchorMode::AtField { x, y });
         let _ = window.show();
-        let _ = window.set_focus();
+        activation::activate_and_focus(&window);
     }
 }
 
@@ -28,41 +32,76 @@ fn toggle_overlay(app: AppHandle) {
         if window.is_visible().unwrap_or(false) {
             let _ = window.hide();
         } else {
-            // Center on screen when triggered via hotkey/tray
-            if let Some(monitor) = window.current_monitor().ok().flatten() {
-                let screen_size = monitor.size();
-                let win_size = window.outer_size().unwrap_or(tauri::PhysicalSize::new(420, 480));
-                let x = (screen_size.width as i32 - win_size.width as i32) / 2;
-                let y = (screen_size.height as i32) / 4;
-                let _ = window.set_position(tauri::PhysicalPosition::new(x, y));
-            }
+            let _ = positioning::position_overlay(&window, AnchorMode::LastPosition);
             let _ = window.show();
-            let _ = window.set_focus();
+            activation::activate_and_focus(&window);
         }
     }
 }
 
 pub fn run() {
-    tauri::Builder::default()
+    let builder = tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
+        .register_uri_scheme_protocol("keynest", |ctx, request| {
+            icon_protocol::handle(ctx.app_handle(), request)
+        })
         .setup(|app| {
+            // Keep Keynest out of the Dock and Cmd+Tab by default.
+            activation::apply(activation::load(&app.handle().clone()));
+
             // Set up system tray
             tray::setup_tray(app)?;
 
-            // Register global hotkey: Cmd+Shift+K
-            overlay::register_global_hotkey(app)?;
+            // Register user-configurable global hotkeys
+            hotkeys::register_hotkeys(app)?;
 
             // Start accessibility observer for native app autofill
             #[cfg(target_os = "macos")]
             accessibility::start_ax_observer(app.handle().clone());
 
+            if let Some(window) = app.get_webview_window("overlay") {
+                let _ = window.set_always_on_top(true);
+                let _ = window.set_decorations(false);
+                let _ = window.set_skip_taskbar(true);
+
+                // Keep the overlay correctly sized/placed when it's dragged
+                // to a monitor with a different scale factor.
+                let moved_window = window.clone();
+                window.on_window_event(move |event| match event {
+                    tauri::WindowEvent::ScaleFactorChanged { .. } | tauri::WindowEvent::Moved(_) => {
+                        let _ = positioning::reclamp_to_current_monitor(&moved_window);
+                    }
+                    _ => {}
+                });
+            }
+
             Ok(())
-        })
-        .invoke_handler(tauri::generate_handler![
-            show_overlay,
-            hide_overlay,
-            toggle_overlay,
-        ])
+        });
+
+    // `generate_handler!` parses a path list and doesn't accept a `#[cfg]`
+    // on one of its entries, so the macOS-only command needs its own
+    // invocation rather than being conditionally spliced into one list.
+    #[cfg(target_os = "macos")]
+    let builder = builder.invoke_handler(tauri::generate_handler![
+        show_overlay,
+        hide_overlay,
+        toggle_overlay,
+        activation::set_activation_policy,
+        tray::update_tray_state,
+        hotkeys::rebind_hotkey,
+        accessibility::fill_focused_field,
+    ]);
+    #[cfg(not(target_os = "macos"))]
+    let builder = builder.invoke_handler(tauri::generate_handler![
+        show_overlay,
+        hide_overlay,
+        toggle_overlay,
+        activation::set_activation_policy,
+        tray::update_tray_state,
+        hotkeys::rebind_hotkey,
+    ]);
+
+    builder
         .run(tauri::generate_context!())
         .expect("error while running Keynest");
 }
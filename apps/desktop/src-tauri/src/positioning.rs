@@ -0,0 +1,209 @@
+/// Overlay positioning subsystem.
+///
+/// `toggle_overlay`, the global hotkey handler, and the tray icon's click
+/// handler all need to place the overlay somewhere sensible before showing
+/// it. Rather than each entry point re-deriving "center on current
+/// monitor" math, they all funnel through [`position_overlay`] with an
+/// [`AnchorMode`] describing *why* the overlay is being shown.
+///
+/// Everything here works in logical coordinates and only converts to
+/// physical pixels for the final `set_position` call, so it stays correct
+/// on fractional-scale and mixed-DPI setups: a 420x480 logical window is
+/// always 420x480 logical, not a too-small 420x480 *physical* window on a
+/// 2x display.
+use serde::{Deserialize, Serialize};
+use std::fs;
+use tauri::{LogicalPosition, LogicalSize, Manager, Monitor, PhysicalPosition, Window};
+
+const DEFAULT_WIDTH: f64 = 420.0;
+const DEFAULT_HEIGHT: f64 = 480.0;
+const LAST_POSITION_FILE: &str = "overlay-position.json";
+
+/// Why the overlay is being shown, and therefore where it should appear.
+#[derive(Clone, Copy, Debug)]
+pub enum AnchorMode {
+    /// Next to the current mouse pointer.
+    NearCursor,
+    /// Next to the point on the tray icon the user clicked (physical pixels,
+    /// as reported by `TrayIconEvent::Click`).
+    NearTrayIcon { x: f64, y: f64 },
+    /// Next to a field the accessibility autofill pipeline focused.
+    /// `AXPositionAttribute`/`AXSizeAttribute` are already in logical
+    /// points, not physical pixels — don't divide these by scale factor
+    /// again.
+    AtField { x: f64, y: f64 },
+    /// Centered on the monitor under the cursor — the original default placement.
+    ScreenCenter,
+    /// Wherever the user last left the overlay.
+    LastPosition,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedPosition {
+    x: f64,
+    y: f64,
+}
+
+/// Moves `window` according to `mode`, clamping the result so the overlay
+/// never spills off its target monitor's work area, then persists the
+/// resulting logical position so a later `AnchorMode::LastPosition` can
+/// restore it.
+pub fn position_overlay(window: &Window, mode: AnchorMode) -> tauri::Result<()> {
+    let found = match mode {
+        AnchorMode::NearTrayIcon { x, y } => monitor_at(window, PhysicalPosition::new(x as i32, y as i32)),
+        AnchorMode::AtField { x, y } => monitor_at_logical(window, LogicalPosition::new(x, y)),
+        _ => monitor_under_cursor(window),
+    };
+    let Some(monitor) = found.or(window.primary_monitor()?) else {
+        return Ok(());
+    };
+
+    let scale_factor = monitor.scale_factor();
+    let target_logical = match mode {
+        AnchorMode::NearCursor => window.cursor_position()?.to_logical::<f64>(scale_factor),
+        AnchorMode::NearTrayIcon { x, y } => PhysicalPosition::new(x, y).to_logical::<f64>(scale_factor),
+        AnchorMode::AtField { x, y } => LogicalPosition::new(x, y),
+        AnchorMode::ScreenCenter => screen_center_logical(window, &monitor),
+        AnchorMode::LastPosition => load_last_position(window).unwrap_or_else(|| screen_center_logical(window, &monitor)),
+    };
+
+    let clamped = clamp_to_monitor_logical(window, &monitor, target_logical);
+    let physical: PhysicalPosition<i32> = clamped.to_physical(scale_factor);
+    window.set_position(physical)?;
+
+    // Only the "default placement" anchors count as where the overlay
+    // normally lives. Field/tray/cursor anchors are one-off placements
+    // driven by where the user clicked or what autofill focused — saving
+    // those would make `LastPosition` restore to wherever a password
+    // field happened to be instead of where the user actually left it.
+    if matches!(mode, AnchorMode::ScreenCenter | AnchorMode::LastPosition) {
+        save_last_position(window, clamped);
+    }
+    Ok(())
+}
+
+/// Re-clamps `window` to whatever monitor it's currently on, using that
+/// monitor's current scale factor. Called in response to
+/// `WindowEvent::ScaleFactorChanged` and when the window is dragged onto a
+/// different monitor, so a DPI change never leaves the overlay mis-sized
+/// or partly off-screen.
+pub fn reclamp_to_current_monitor(window: &Window) -> tauri::Result<()> {
+    let Some(monitor) = window.current_monitor()? else {
+        return Ok(());
+    };
+    let scale_factor = monitor.scale_factor();
+    let Ok(current_physical) = window.outer_position() else {
+        return Ok(());
+    };
+    let current_logical: LogicalPosition<f64> = current_physical.to_logical(scale_factor);
+
+    let clamped = clamp_to_monitor_logical(window, &monitor, current_logical);
+    if (clamped.x - current_logical.x).abs() > f64::EPSILON
+        || (clamped.y - current_logical.y).abs() > f64::EPSILON
+    {
+        let physical: PhysicalPosition<i32> = clamped.to_physical(scale_factor);
+        window.set_position(physical)?;
+    }
+    save_last_position(window, clamped);
+    Ok(())
+}
+
+/// The window's logical size, falling back to the design default if the
+/// window hasn't reported a real size yet (e.g. during early setup).
+fn logical_size(window: &Window, monitor: &Monitor) -> LogicalSize<f64> {
+    window
+        .outer_size()
+        .map(|size| size.to_logical::<f64>(monitor.scale_factor()))
+        .unwrap_or(LogicalSize::new(DEFAULT_WIDTH, DEFAULT_HEIGHT))
+}
+
+/// The original "center horizontally, sit at a quarter of the screen
+/// height" placement, computed in logical units on `monitor`.
+fn screen_center_logical(window: &Window, monitor: &Monitor) -> LogicalPosition<f64> {
+    let scale_factor = monitor.scale_factor();
+    let screen_size = monitor.size().to_logical::<f64>(scale_factor);
+    let win_size = logical_size(window, monitor);
+    let x = (screen_size.width - win_size.width) / 2.0;
+    let y = screen_size.height / 4.0;
+    LogicalPosition::new(x, y)
+}
+
+/// Clamps `target` (logical) so the window's full outer rect stays inside
+/// `monitor`'s logical work area.
+fn clamp_to_monitor_logical(
+    window: &Window,
+    monitor: &Monitor,
+    target: LogicalPosition<f64>,
+) -> LogicalPosition<f64> {
+    let scale_factor = monitor.scale_factor();
+    let work_area = monitor.work_area().to_logical::<f64>(scale_factor);
+    let win_size = logical_size(window, monitor);
+
+    let max_x = work_area.position.x + work_area.size.width - win_size.width;
+    let max_y = work_area.position.y + work_area.size.height - win_size.height;
+
+    LogicalPosition::new(
+        target.x.clamp(work_area.position.x, max_x.max(work_area.position.x)),
+        target.y.clamp(work_area.position.y, max_y.max(work_area.position.y)),
+    )
+}
+
+/// Finds the monitor whose physical bounds contain `point`, if any.
+fn monitor_at(window: &Window, point: PhysicalPosition<i32>) -> Option<Monitor> {
+    window.available_monitors().ok()?.into_iter().find(|monitor| {
+        let pos = monitor.position();
+        let size = monitor.size();
+        point.x >= pos.x
+            && point.x < pos.x + size.width as i32
+            && point.y >= pos.y
+            && point.y < pos.y + size.height as i32
+    })
+}
+
+/// Finds the monitor whose logical bounds contain `point`, for callers
+/// (like the AX autofill pipeline) that already have a point in logical
+/// coordinates rather than physical pixels.
+fn monitor_at_logical(window: &Window, point: LogicalPosition<f64>) -> Option<Monitor> {
+    window.available_monitors().ok()?.into_iter().find(|monitor| {
+        let scale_factor = monitor.scale_factor();
+        let pos = monitor.position().to_logical::<f64>(scale_factor);
+        let size = monitor.size().to_logical::<f64>(scale_factor);
+        point.x >= pos.x
+            && point.x < pos.x + size.width
+            && point.y >= pos.y
+            && point.y < pos.y + size.height
+    })
+}
+
+/// The monitor the mouse pointer is currently over, which may differ from
+/// `window.current_monitor()` (the monitor the *window* is on).
+fn monitor_under_cursor(window: &Window) -> Option<Monitor> {
+    let cursor = window.cursor_position().ok()?;
+    monitor_at(window, PhysicalPosition::new(cursor.x as i32, cursor.y as i32))
+}
+
+fn position_file(window: &Window) -> Option<std::path::PathBuf> {
+    let dir = window.app_handle().path().app_config_dir().ok()?;
+    fs::create_dir_all(&dir).ok()?;
+    Some(dir.join(LAST_POSITION_FILE))
+}
+
+fn load_last_position(window: &Window) -> Option<LogicalPosition<f64>> {
+    let path = position_file(window)?;
+    let contents = fs::read_to_string(path).ok()?;
+    let saved: SavedPosition = serde_json::from_str(&contents).ok()?;
+    Some(LogicalPosition::new(saved.x, saved.y))
+}
+
+fn save_last_position(window: &Window, position: LogicalPosition<f64>) {
+    let Some(path) = position_file(window) else {
+        return;
+    };
+    let saved = SavedPosition {
+        x: position.x,
+        y: position.y,
+    };
+    if let Ok(json) = serde_json::to_string(&saved) {
+        let _ = fs::write(path, json);
+    }
+}
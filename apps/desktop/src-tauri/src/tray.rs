@@ -1,11 +1,25 @@
+use crate::positioning::{self, AnchorMode};
+use std::sync::Mutex;
 use tauri::{
-    App,
-    Emitter,
+    image::Image,
     menu::{Menu, MenuItem},
-    tray::{TrayIconBuilder, TrayIconEvent},
-    Manager,
+    tray::{TrayIcon, TrayIconBuilder, TrayIconEvent},
+    App, AppHandle, Emitter, Manager, Wry,
 };
 
+const LOCKED_ICON: &[u8] = include_bytes!("../icons/tray-locked.png");
+const UNLOCKED_ICON: &[u8] = include_bytes!("../icons/tray-unlocked.png");
+
+/// Tray menu items, icon handle, and the lock state they currently reflect,
+/// kept around so `update_tray_state` can relabel/re-skin the existing
+/// tray instead of tearing down and rebuilding it.
+struct TrayState {
+    open: MenuItem<Wry>,
+    lock: MenuItem<Wry>,
+    icon: TrayIcon<Wry>,
+    locked: Mutex<bool>,
+}
+
 pub fn setup_tray(app: &mut App) -> Result<(), Box<dyn std::error::Error>> {
     let open = MenuItem::with_id(app, "open", "Open Keynest", true, None::<&str>)?;
     let lock = MenuItem::with_id(app, "lock", "Lock Vault", true, None::<&str>)?;
@@ -13,28 +27,22 @@ pub fn setup_tray(app: &mut App) -> Result<(), Box<dyn std::error::Error>> {
 
     let menu = Menu::with_items(app, &[&open, &lock, &quit])?;
 
-    TrayIconBuilder::new()
+    let icon = TrayIconBuilder::new()
         .icon(app.default_window_icon().unwrap().clone())
         .menu(&menu)
         .on_tray_icon_event(|tray, event| {
-            if let TrayIconEvent::Click { .. } = event {
+            if let TrayIconEvent::Click { position, .. } = event {
                 let app = tray.app_handle();
-                if let Some(window) = app.get_webview_window("overlay") {
-                    let _ = window.show();
-                    let _ = window.set_focus();
-                }
+                toggle_overlay_near(app, position.x, position.y);
             }
         })
         .on_menu_event(|app, event| match event.id.as_ref() {
-            "open" => {
-                if let Some(window) = app.get_webview_window("overlay") {
-                    let _ = window.show();
-                    let _ = window.set_focus();
-                }
-            }
+            "open" => toggle_overlay_near(app, 0.0, 0.0),
             "lock" => {
-                // Emit lock event to frontend
-                let _ = app.emit("vault:lock", ());
+                let state = app.state::<TrayState>();
+                let locked = *state.locked.lock().unwrap();
+                let event_name = if locked { "vault:unlock" } else { "vault:lock" };
+                let _ = app.emit(event_name, ());
             }
             "quit" => {
                 app.exit(0);
@@ -43,5 +51,55 @@ pub fn setup_tray(app: &mut App) -> Result<(), Box<dyn std::error::Error>> {
         })
         .build(app)?;
 
+    app.manage(TrayState {
+        open,
+        lock,
+        icon,
+        locked: Mutex::new(false),
+    });
+
+    Ok(())
+}
+
+/// Shows the overlay near `(x, y)` if it's hidden, hides it otherwise —
+/// used by both the tray icon's click and the "Open Keynest"/"Hide" menu
+/// item, which the click position defaults don't reach for (so the menu
+/// item falls back to `LastPosition`).
+fn toggle_overlay_near(app: &AppHandle, x: f64, y: f64) {
+    let Some(window) = app.get_webview_window("overlay") else {
+        return;
+    };
+    if window.is_visible().unwrap_or(false) {
+        let _ = window.hide();
+        return;
+    }
+    let mode = if x == 0.0 && y == 0.0 {
+        AnchorMode::LastPosition
+    } else {
+        AnchorMode::NearTrayIcon { x, y }
+    };
+    let _ = positioning::position_overlay(&window, mode);
+    let _ = window.show();
+    crate::activation::activate_and_focus(&window);
+}
+
+/// Called by the frontend whenever the vault's lock state or the overlay's
+/// visibility changes, so the tray menu text and icon stay truthful
+/// without the frontend having to know about `MenuItem` handles.
+#[tauri::command]
+pub fn update_tray_state(app: AppHandle, locked: bool, visible: bool) -> Result<(), String> {
+    let state = app.state::<TrayState>();
+
+    let lock_label = if locked { "Unlock Vault" } else { "Lock Vault" };
+    state.lock.set_text(lock_label).map_err(|e| e.to_string())?;
+
+    let open_label = if visible { "Hide" } else { "Open Keynest" };
+    state.open.set_text(open_label).map_err(|e| e.to_string())?;
+
+    let icon_bytes = if locked { LOCKED_ICON } else { UNLOCKED_ICON };
+    let icon = Image::from_bytes(icon_bytes).map_err(|e| e.to_string())?;
+    state.icon.set_icon(Some(icon)).map_err(|e| e.to_string())?;
+
+    *state.locked.lock().unwrap() = locked;
     Ok(())
 }